@@ -33,3 +33,15 @@ pub trait Headered {
     /// Get the block header.
     fn header(&self) -> Self::Header;
 }
+
+/// A block that carries a body of extrinsics.
+///
+/// This allows executors, transaction pools and other components to inspect
+/// the block content generically, without knowing the concrete block type.
+pub trait Bodied {
+    /// Extrinsic type.
+    type Extrinsic;
+
+    /// Get the extrinsics of the block body.
+    fn extrinsics(&self) -> &[Self::Extrinsic];
+}
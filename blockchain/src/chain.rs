@@ -26,6 +26,16 @@ pub trait ForkTree {
         id: &<Self::Block as Identified>::Identifier,
     ) -> Result<usize, Self::QueryError>;
 
+    /// Get the parent id of a block by its id. None if the block is genesis.
+    ///
+    /// Implementations should override this if getting a block is expensive.
+    fn parent_id(
+        &self,
+        id: &<Self::Block as Identified>::Identifier,
+    ) -> Result<Option<<Self::Block as Identified>::Identifier>, Self::QueryError> {
+        Ok(self.block(id)?.parent_id())
+    }
+
     /// Find an ancestor block at given depth.
     ///
     /// If ancestor depth equals the provided block's depth, return the provided block ID.
@@ -47,6 +57,181 @@ pub trait ForkTree {
 
         Ok(self.ancestor_id_at_depth(id, ancestor_depth)? == *ancestor_id)
     }
+
+    /// Iterate over ancestors of a block, starting from its parent and ending
+    /// at the root of the fork tree.
+    fn ancestors(&self, id: &<Self::Block as Identified>::Identifier) -> Ancestors<'_, Self>
+    where
+        Self: Sized,
+    {
+        Ancestors {
+            fork_tree: self,
+            current_id: Some(*id),
+        }
+    }
+
+    /// Find the common ancestor with the greatest depth of two blocks.
+    ///
+    /// If one block is the ancestor of the other, return that block. Return
+    /// `None` if the two blocks do not share a root.
+    fn common_ancestor(
+        &self,
+        id: &<Self::Block as Identified>::Identifier,
+        other_id: &<Self::Block as Identified>::Identifier,
+    ) -> Result<Option<<Self::Block as Identified>::Identifier>, Self::QueryError> {
+        let is_common_at = |depth| -> Result<bool, Self::QueryError> {
+            Ok(self.ancestor_id_at_depth(id, depth)?
+                == self.ancestor_id_at_depth(other_id, depth)?)
+        };
+
+        if !is_common_at(0)? {
+            return Ok(None);
+        }
+
+        // Blocks sharing an ancestor at some depth also share ancestors at all
+        // lower depths, so we can binary search for the greatest one.
+        let mut low = 0;
+        let mut high = core::cmp::min(self.block_depth(id)?, self.block_depth(other_id)?);
+        while low < high {
+            let mid = low + (high - low + 1) / 2;
+            if is_common_at(mid)? {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+
+        Ok(Some(self.ancestor_id_at_depth(id, low)?))
+    }
+
+    /// Find the route from one block to another through their common
+    /// ancestor. Return `None` if the two blocks do not share a root.
+    fn tree_route(
+        &self,
+        from: &<Self::Block as Identified>::Identifier,
+        to: &<Self::Block as Identified>::Identifier,
+    ) -> Result<Option<TreeRoute<Self>>, Self::QueryError>
+    where
+        Self: Sized,
+    {
+        let pivot_id = match self.common_ancestor(from, to)? {
+            Some(pivot_id) => pivot_id,
+            None => return Ok(None),
+        };
+        let pivot_depth = self.block_depth(&pivot_id)?;
+
+        // Collect blocks from the given block down to, but excluding, the
+        // pivot.
+        let leg = |id: &<Self::Block as Identified>::Identifier| {
+            let mut depth = self.block_depth(id)?;
+            let mut route = Vec::with_capacity(depth - pivot_depth);
+            let ancestors = core::iter::once(Ok(*id)).chain(self.ancestors(id));
+            for ancestor_id in ancestors.take(depth - pivot_depth) {
+                route.push((ancestor_id?, depth));
+                depth -= 1;
+            }
+            Ok(route)
+        };
+
+        let mut route = leg(from)?;
+        let pivot = route.len();
+        route.push((pivot_id, pivot_depth));
+        route.extend(leg(to)?.into_iter().rev());
+
+        Ok(Some(TreeRoute { route, pivot }))
+    }
+}
+
+/// Route between two blocks in a fork tree, created by
+/// [`ForkTree::tree_route`].
+///
+/// The route goes from the starting block down to the common ancestor (the
+/// pivot), and then up to the target block. Each block is paired with its
+/// depth.
+pub struct TreeRoute<FT: ForkTree> {
+    route: Vec<(<FT::Block as Identified>::Identifier, usize)>,
+    pivot: usize,
+}
+
+impl<FT: ForkTree> TreeRoute<FT> {
+    /// Get the common ancestor of the two blocks.
+    pub fn pivot(&self) -> &(<FT::Block as Identified>::Identifier, usize) {
+        &self.route[self.pivot]
+    }
+
+    /// Get blocks retracted when moving from the starting block, ordered from
+    /// the starting block down to the child of the pivot.
+    pub fn retracted(&self) -> &[(<FT::Block as Identified>::Identifier, usize)] {
+        &self.route[..self.pivot]
+    }
+
+    /// Get blocks enacted when moving to the target block, ordered from the
+    /// child of the pivot up to the target block.
+    pub fn enacted(&self) -> &[(<FT::Block as Identified>::Identifier, usize)] {
+        &self.route[self.pivot + 1..]
+    }
+}
+
+// Implemented manually, because deriving would require `FT` itself to
+// implement these traits.
+impl<FT: ForkTree> Clone for TreeRoute<FT> {
+    fn clone(&self) -> Self {
+        Self {
+            route: self.route.clone(),
+            pivot: self.pivot,
+        }
+    }
+}
+
+impl<FT: ForkTree> core::fmt::Debug for TreeRoute<FT>
+where
+    <FT::Block as Identified>::Identifier: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TreeRoute")
+            .field("route", &self.route)
+            .field("pivot", &self.pivot)
+            .finish()
+    }
+}
+
+impl<FT: ForkTree> IntoIterator for TreeRoute<FT> {
+    type Item = (<FT::Block as Identified>::Identifier, usize);
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.route.into_iter()
+    }
+}
+
+/// Iterator over ancestors of a block, created by [`ForkTree::ancestors`].
+pub struct Ancestors<'ft, FT: ForkTree> {
+    fork_tree: &'ft FT,
+    current_id: Option<<FT::Block as Identified>::Identifier>,
+}
+
+impl<'ft, FT: ForkTree> Iterator for Ancestors<'ft, FT> {
+    type Item = Result<<FT::Block as Identified>::Identifier, FT::QueryError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current_id = self.current_id.take()?;
+
+        match self.fork_tree.parent_id(&current_id) {
+            Ok(parent_id) => {
+                self.current_id = parent_id;
+                self.current_id.map(Ok)
+            }
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// Fork tree that tracks its leaves.
+pub trait ForkTreeLeaves: ForkTree {
+    /// Get all leaves of the fork tree, that is, blocks without any children.
+    ///
+    /// Leaves are returned in no particular order.
+    fn leaves(&self) -> Result<Vec<<Self::Block as Identified>::Identifier>, Self::QueryError>;
 }
 
 /// A structure representing a chain with possible forks.
@@ -84,6 +269,29 @@ pub trait ImportBlock {
     fn import(&mut self, block: Self::Block) -> Result<(), Self::Error>;
 }
 
+/// Block verifier.
+///
+/// A verifier checks consensus rules of a block, such as seals or timestamp
+/// bounds, before it is executed and imported.
+pub trait BlockVerifier {
+    /// Type of the chain.
+    type Chain;
+    /// Type of the block.
+    type Block: Identified;
+    /// Type of the verified block, passed on to import.
+    type VerifiedBlock;
+    /// Error type.
+    type Error;
+
+    /// Verify a block against the chain it is imported into. The parent block
+    /// and its state are looked up in the chain by the block's parent id.
+    fn verify(
+        &self,
+        chain: &Self::Chain,
+        block: Self::Block,
+    ) -> Result<Self::VerifiedBlock, Self::Error>;
+}
+
 /// Block builder.
 pub trait BlockBuilder<'chain>: Sized {
     /// Type of the chain.
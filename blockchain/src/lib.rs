@@ -7,6 +7,11 @@ mod chain;
 pub mod memory;
 mod state;
 
-pub use crate::block::{Headered, Identified, Keyed};
-pub use crate::chain::{BlockBuilder, ForkTree, ForkTreeMut, ForkTreeTransactional, ImportBlock};
-pub use crate::state::{FlatState, FlatStateMut, FlatStateTransactional, OverlayedFlatState};
+pub use crate::block::{Bodied, Headered, Identified, Keyed};
+pub use crate::chain::{
+    Ancestors, BlockBuilder, BlockVerifier, ForkTree, ForkTreeLeaves, ForkTreeMut,
+    ForkTreeTransactional, ImportBlock, TreeRoute,
+};
+pub use crate::state::{
+    FlatState, FlatStateMut, FlatStateTransactional, NoOpenTransaction, OverlayedFlatState,
+};
@@ -1,7 +1,7 @@
 use itertools::Itertools;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use crate::{ForkTree, ForkTreeMut, Identified};
+use crate::{ForkTree, ForkTreeLeaves, ForkTreeMut, Identified};
 
 #[derive(Clone, Debug)]
 struct MemoryForkTreeItem<Block: Identified> {
@@ -16,6 +16,7 @@ struct MemoryForkTreeItem<Block: Identified> {
 pub struct MemoryForkTree<Block: Identified> {
     blocks: HashMap<Block::Identifier, MemoryForkTreeItem<Block>>,
     depths: HashMap<usize, Vec<Block::Identifier>>,
+    leaves: HashSet<Block::Identifier>,
 }
 
 impl<Block: Identified> MemoryForkTree<Block> {
@@ -24,8 +25,69 @@ impl<Block: Identified> MemoryForkTree<Block> {
         Self {
             blocks: HashMap::new(),
             depths: HashMap::new(),
+            leaves: HashSet::new(),
         }
     }
+
+    /// Get statistics of the fork tree.
+    pub fn info(&self) -> MemoryForkTreeInfo {
+        MemoryForkTreeInfo {
+            blocks: self.blocks.len(),
+            leaves: self.leaves.len(),
+            max_depth: self.depths.keys().max().cloned(),
+            forks: self
+                .depths
+                .iter()
+                .filter(|(_, ids)| ids.len() > 1)
+                .map(|(depth, ids)| (*depth, ids.len()))
+                .collect(),
+        }
+    }
+}
+
+// Blocks are serialized as a sequence ordered by depth and id, so that the
+// same fork tree always gives the same output. Deserialization inserts the
+// blocks again, rebuilding children, ancestors, depths and leaves.
+#[cfg(feature = "serde")]
+impl<Block> serde::Serialize for MemoryForkTree<Block>
+where
+    Block: Identified + serde::Serialize,
+    Block::Identifier: Ord,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut items = self.blocks.iter().collect::<Vec<_>>();
+        items.sort_by_key(|(id, item)| (item.depth, **id));
+
+        serializer.collect_seq(items.into_iter().map(|(_, item)| &item.block))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, Block> serde::Deserialize<'de> for MemoryForkTree<Block>
+where
+    Block: Identified + Clone + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut fork_tree = Self::new();
+        for block in Vec::<Block>::deserialize(deserializer)? {
+            fork_tree.insert(block).map_err(serde::de::Error::custom)?;
+        }
+
+        Ok(fork_tree)
+    }
+}
+
+/// Statistics of a memory fork tree.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MemoryForkTreeInfo {
+    /// Total number of blocks.
+    pub blocks: usize,
+    /// Number of leaves.
+    pub leaves: usize,
+    /// Greatest depth of all blocks. None if the fork tree is empty.
+    pub max_depth: Option<usize>,
+    /// Number of blocks at each depth where there is more than one block.
+    pub forks: BTreeMap<usize, usize>,
 }
 
 /// Query error for memory fork tree.
@@ -37,6 +99,17 @@ pub enum MemoryForkTreeQueryError {
     InvalidAncestorDepth,
 }
 
+impl core::fmt::Display for MemoryForkTreeQueryError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnknownBlock => write!(f, "unknown block"),
+            Self::InvalidAncestorDepth => write!(f, "invalid ancestor depth"),
+        }
+    }
+}
+
+impl std::error::Error for MemoryForkTreeQueryError {}
+
 impl<Block: Identified + Clone> ForkTree for MemoryForkTree<Block> {
     type Block = Block;
     type QueryError = MemoryForkTreeQueryError;
@@ -58,6 +131,18 @@ impl<Block: Identified + Clone> ForkTree for MemoryForkTree<Block> {
             .depth)
     }
 
+    fn parent_id(
+        &self,
+        id: &Block::Identifier,
+    ) -> Result<Option<Block::Identifier>, Self::QueryError> {
+        Ok(self
+            .blocks
+            .get(id)
+            .ok_or(MemoryForkTreeQueryError::UnknownBlock)?
+            .block
+            .parent_id())
+    }
+
     fn ancestor_id_at_depth(
         &self,
         id: &Block::Identifier,
@@ -68,10 +153,6 @@ impl<Block: Identified + Clone> ForkTree for MemoryForkTree<Block> {
             .get(&id)
             .ok_or(MemoryForkTreeQueryError::UnknownBlock)?;
 
-        if current_block.depth > ancestor_depth {
-            return Err(MemoryForkTreeQueryError::InvalidAncestorDepth);
-        }
-
         loop {
             if current_block.depth < ancestor_depth {
                 return Err(MemoryForkTreeQueryError::InvalidAncestorDepth);
@@ -114,9 +195,17 @@ impl<Block: Identified + Clone> ForkTree for MemoryForkTree<Block> {
     }
 }
 
+impl<Block: Identified + Clone> ForkTreeLeaves for MemoryForkTree<Block> {
+    fn leaves(&self) -> Result<Vec<Block::Identifier>, Self::QueryError> {
+        Ok(self.leaves.iter().cloned().collect())
+    }
+}
+
 /// Insert error for memory fork tree.
 #[derive(Debug, Clone)]
 pub enum MemoryForkTreeInsertError {
+    /// Block is already in the fork tree.
+    AlreadyExists,
     /// Parent is unknown.
     UnknownParent,
     /// Encounted a query issue in insertion.
@@ -129,6 +218,25 @@ impl From<MemoryForkTreeQueryError> for MemoryForkTreeInsertError {
     }
 }
 
+impl core::fmt::Display for MemoryForkTreeInsertError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::AlreadyExists => write!(f, "block already exists"),
+            Self::UnknownParent => write!(f, "unknown parent"),
+            Self::Query(_) => write!(f, "query failed during insertion"),
+        }
+    }
+}
+
+impl std::error::Error for MemoryForkTreeInsertError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::AlreadyExists | Self::UnknownParent => None,
+            Self::Query(query) => Some(query),
+        }
+    }
+}
+
 /// Skip depths for ancestor list.
 const SKIP_DEPTHS: [usize; 16] = [
     4usize.pow(1),
@@ -155,6 +263,10 @@ impl<Block: Identified + Clone> ForkTreeMut for MemoryForkTree<Block> {
     fn insert(&mut self, block: Block) -> Result<(), Self::InsertError> {
         let block_id = block.id();
 
+        if self.blocks.contains_key(&block_id) {
+            return Err(MemoryForkTreeInsertError::AlreadyExists);
+        }
+
         let depth = if let Some(parent_id) = block.parent_id() {
             let parent = self
                 .blocks
@@ -189,6 +301,10 @@ impl<Block: Identified + Clone> ForkTreeMut for MemoryForkTree<Block> {
             Vec::new()
         };
 
+        if let Some(parent_id) = block.parent_id() {
+            self.leaves.remove(&parent_id);
+        }
+        self.leaves.insert(block_id);
         self.depths.entry(depth).or_default().push(block_id);
         self.blocks.insert(
             block_id,
@@ -3,8 +3,10 @@
 mod chain;
 mod state;
 
-pub use self::chain::{MemoryForkTree, MemoryForkTreeInsertError, MemoryForkTreeQueryError};
-pub use self::state::MemoryFlatState;
+pub use self::chain::{
+    MemoryForkTree, MemoryForkTreeInfo, MemoryForkTreeInsertError, MemoryForkTreeQueryError,
+};
+pub use self::state::{MemoryFlatState, MemoryFlatStateDiff};
 
 use core::ops::{Deref, DerefMut};
 
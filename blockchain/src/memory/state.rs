@@ -10,6 +10,10 @@ pub struct MemoryFlatState<K, V, Identifier> {
     state: HashMap<K, BTreeMap<usize, HashMap<Identifier, Option<V>>>>,
 }
 
+/// Differences of the state between two blocks, as key, value at the first
+/// block, and value at the second block.
+pub type MemoryFlatStateDiff<K, V> = Vec<(K, Option<V>, Option<V>)>;
+
 impl<K, V, Identifier> MemoryFlatState<K, V, Identifier>
 where
     K: Eq + PartialEq + Hash,
@@ -22,6 +26,133 @@ where
             state: HashMap::new(),
         }
     }
+
+    /// Export all key-value pairs of the state at particular block id,
+    /// ordered by key.
+    pub fn export<FT, B>(
+        &self,
+        block_id: &Identifier,
+        fork_tree: &FT,
+    ) -> Result<Vec<(K, V)>, FT::QueryError>
+    where
+        K: Ord + Clone,
+        FT: ForkTree<Block = B>,
+        B: Identified<Identifier = Identifier>,
+    {
+        let mut keys = self.state.keys().cloned().collect::<Vec<_>>();
+        keys.sort();
+        let values = self.get_multi(&keys, block_id, fork_tree)?;
+
+        Ok(keys
+            .into_iter()
+            .zip(values)
+            .filter_map(|(key, value)| Some((key, value?)))
+            .collect())
+    }
+
+    /// Get the differences of the state between two blocks, ordered by key.
+    ///
+    /// Only keys changed by blocks on the tree route between the two blocks
+    /// are checked.
+    pub fn diff<FT, B>(
+        &self,
+        from: &Identifier,
+        to: &Identifier,
+        fork_tree: &FT,
+    ) -> Result<MemoryFlatStateDiff<K, V>, FT::QueryError>
+    where
+        K: Ord + Clone,
+        V: PartialEq,
+        FT: ForkTree<Block = B>,
+        B: Identified<Identifier = Identifier>,
+    {
+        let route = fork_tree.tree_route(from, to)?;
+        let mut keys = self
+            .state
+            .iter()
+            .filter(|(_, depth_to_id_value)| match &route {
+                Some(route) => {
+                    route
+                        .retracted()
+                        .iter()
+                        .chain(route.enacted())
+                        .any(|(id, depth)| {
+                            depth_to_id_value
+                                .get(depth)
+                                .map_or(false, |id_to_value| id_to_value.contains_key(id))
+                        })
+                }
+                // Without a common ancestor, any key may differ.
+                None => true,
+            })
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        keys.sort();
+
+        let from_values = self.get_multi(&keys, from, fork_tree)?;
+        let to_values = self.get_multi(&keys, to, fork_tree)?;
+
+        Ok(keys
+            .into_iter()
+            .zip(from_values.into_iter().zip(to_values))
+            .filter(|(_, (from_value, to_value))| from_value != to_value)
+            .map(|(key, (from_value, to_value))| (key, from_value, to_value))
+            .collect())
+    }
+}
+
+// Values are serialized as a sequence of key, depth, block id and value,
+// ordered by all but the value, so that the same state always gives the same
+// output.
+#[cfg(feature = "serde")]
+impl<K, V, Identifier> serde::Serialize for MemoryFlatState<K, V, Identifier>
+where
+    K: Ord + serde::Serialize,
+    V: serde::Serialize,
+    Identifier: Ord + serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut entries = self
+            .state
+            .iter()
+            .flat_map(|(key, depth_to_id_value)| {
+                depth_to_id_value
+                    .iter()
+                    .flat_map(move |(depth, id_to_value)| {
+                        id_to_value
+                            .iter()
+                            .map(move |(id, value)| (key, depth, id, value))
+                    })
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|(key, depth, id, _)| (*key, *depth, *id));
+
+        serializer.collect_seq(entries)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K, V, Identifier> serde::Deserialize<'de> for MemoryFlatState<K, V, Identifier>
+where
+    K: Eq + PartialEq + Hash + serde::Deserialize<'de>,
+    V: serde::Deserialize<'de>,
+    Identifier: Eq + PartialEq + Hash + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut state = HashMap::<K, BTreeMap<usize, HashMap<Identifier, Option<V>>>>::new();
+        for (key, depth, id, value) in
+            Vec::<(K, usize, Identifier, Option<V>)>::deserialize(deserializer)?
+        {
+            state
+                .entry(key)
+                .or_default()
+                .entry(depth)
+                .or_default()
+                .insert(id, value);
+        }
+
+        Ok(Self { state })
+    }
 }
 
 impl<K, V, Identifier, FT, B> FlatState<FT> for MemoryFlatState<K, V, Identifier>
@@ -44,21 +175,66 @@ where
     ) -> Result<Option<Self::Value>, Self::QueryError> {
         if let Some(depth_to_id_value) = self.state.get(key) {
             let depth = fork_tree.block_depth(block_id)?;
-            let search_range = depth_to_id_value
-                .range((Bound::Unbounded, Bound::Included(depth)))
-                .rev();
-
-            for (_, search_id_to_value) in search_range {
-                for (search_id, search_value) in search_id_to_value {
-                    if fork_tree.is_ancestor(block_id, search_id)? {
-                        return Ok(search_value.clone());
+            search(depth_to_id_value, depth, |search_id| {
+                fork_tree.is_ancestor(block_id, search_id)
+            })
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn get_multi(
+        &self,
+        keys: &[Self::Key],
+        block_id: &<FT::Block as Identified>::Identifier,
+        fork_tree: &FT,
+    ) -> Result<Vec<Option<Self::Value>>, Self::QueryError> {
+        let depth = fork_tree.block_depth(block_id)?;
+        // Keys are often changed together in the same blocks, so we remember
+        // ancestry checks across keys.
+        let mut ancestry = HashMap::new();
+
+        keys.iter()
+            .map(|key| match self.state.get(key) {
+                Some(depth_to_id_value) => search(depth_to_id_value, depth, |search_id| {
+                    if let Some(is_ancestor) = ancestry.get(search_id) {
+                        return Ok(*is_ancestor);
                     }
-                }
+
+                    let is_ancestor = fork_tree.is_ancestor(block_id, search_id)?;
+                    ancestry.insert(search_id.clone(), is_ancestor);
+                    Ok(is_ancestor)
+                }),
+                None => Ok(None),
+            })
+            .collect()
+    }
+}
+
+/// Search the value changed at the greatest depth not above the given depth,
+/// by a block accepted by `is_ancestor`.
+fn search<V, Identifier, E, F>(
+    depth_to_id_value: &BTreeMap<usize, HashMap<Identifier, Option<V>>>,
+    depth: usize,
+    mut is_ancestor: F,
+) -> Result<Option<V>, E>
+where
+    V: Clone,
+    F: FnMut(&Identifier) -> Result<bool, E>,
+{
+    let search_range = depth_to_id_value
+        .range((Bound::Unbounded, Bound::Included(depth)))
+        .rev();
+
+    for (_, search_id_to_value) in search_range {
+        for (search_id, search_value) in search_id_to_value {
+            if is_ancestor(search_id)? {
+                return Ok(search_value.clone());
             }
         }
-
-        Ok(None)
     }
+
+    Ok(None)
 }
 
 impl<K, V, Identifier, FT, B> FlatStateMut<FT> for MemoryFlatState<K, V, Identifier>
@@ -23,6 +23,19 @@ pub trait FlatState<FT: ForkTree> {
         fork_tree: &FT,
     ) -> Result<Option<Self::Value>, Self::QueryError>;
 
+    /// Get values of multiple keys at particular block id, in the same order
+    /// as the keys.
+    fn get_multi(
+        &self,
+        keys: &[Self::Key],
+        block_id: &<FT::Block as Identified>::Identifier,
+        fork_tree: &FT,
+    ) -> Result<Vec<Option<Self::Value>>, Self::QueryError> {
+        keys.iter()
+            .map(|key| self.get(key, block_id, fork_tree))
+            .collect()
+    }
+
     /// Overlayed state.
     fn overlayed<'fs, 'ft>(
         &'fs self,
@@ -34,6 +47,7 @@ pub trait FlatState<FT: ForkTree> {
             block_id,
             fork_tree,
             changeset: HashMap::new(),
+            transactions: Vec::new(),
         }
     }
 }
@@ -75,8 +89,26 @@ pub struct OverlayedFlatState<'fs, 'ft, FS: FlatState<FT> + ?Sized, FT: ForkTree
     fork_tree: &'ft FT,
     block_id: <FT::Block as Identified>::Identifier,
     changeset: HashMap<FS::Key, Option<FS::Value>>,
+    transactions: Vec<TransactionLog<FS::Key, FS::Value>>,
 }
 
+/// Changeset entries as they were before an open transaction first touched
+/// them. `None` means the key was not in the changeset.
+type TransactionLog<K, V> = HashMap<K, Option<Option<V>>>;
+
+/// Error returned when committing or rolling back an overlay transaction
+/// while none is open.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct NoOpenTransaction;
+
+impl core::fmt::Display for NoOpenTransaction {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "no open transaction")
+    }
+}
+
+impl std::error::Error for NoOpenTransaction {}
+
 impl<'fs, 'ft, FS, FT> OverlayedFlatState<'fs, 'ft, FS, FT>
 where
     FS: FlatState<FT> + ?Sized,
@@ -95,15 +127,61 @@ where
 
     /// Insert a new value.
     pub fn insert(&mut self, key: FS::Key, value: FS::Value) {
+        self.record(&key);
         self.changeset.insert(key, Some(value));
     }
 
     /// Remove an existing value.
     pub fn remove(&mut self, key: &FS::Key) {
+        self.record(key);
         self.changeset.insert(key.clone(), None);
     }
 
+    /// Start a new transaction. Transactions can be nested.
+    pub fn start_transaction(&mut self) {
+        self.transactions.push(HashMap::new());
+    }
+
+    /// Commit the innermost transaction, keeping its changes.
+    pub fn commit_transaction(&mut self) -> Result<(), NoOpenTransaction> {
+        let committed = self.transactions.pop().ok_or(NoOpenTransaction)?;
+
+        if let Some(parent) = self.transactions.last_mut() {
+            for (key, original) in committed {
+                parent.entry(key).or_insert(original);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Roll back the innermost transaction, discarding its changes.
+    pub fn rollback_transaction(&mut self) -> Result<(), NoOpenTransaction> {
+        let rolled_back = self.transactions.pop().ok_or(NoOpenTransaction)?;
+
+        for (key, original) in rolled_back {
+            match original {
+                Some(value) => self.changeset.insert(key, value),
+                None => self.changeset.remove(&key),
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Record the original changeset entry of a key in the innermost
+    /// transaction, if it is not yet recorded.
+    fn record(&mut self, key: &FS::Key) {
+        if let Some(transaction) = self.transactions.last_mut() {
+            if !transaction.contains_key(key) {
+                transaction.insert(key.clone(), self.changeset.get(key).cloned());
+            }
+        }
+    }
+
     /// Into changeset.
+    ///
+    /// Changes made in transactions that are still open are included.
     pub fn into_changeset(self) -> impl Iterator<Item = (FS::Key, Option<FS::Value>)> {
         self.changeset.into_iter()
     }
@@ -0,0 +1,258 @@
+//! Flat state queries and overlays over a single and a forked chain.
+
+use blockchain::memory::{MemoryFlatState, MemoryForkTree, MemoryForkTreeQueryError};
+use blockchain::{FlatState, FlatStateMut, ForkTreeMut, Identified, NoOpenTransaction};
+
+/// Block identified by its number.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Block {
+    pub number: u32,
+}
+
+impl Identified for Block {
+    type Identifier = u32;
+
+    fn id(&self) -> u32 {
+        self.number
+    }
+
+    fn parent_id(&self) -> Option<u32> {
+        self.number.checked_sub(1)
+    }
+}
+
+/// Build a chain of blocks 0 to 2, where block `n` sets key `n` to `n`.
+fn build_chain() -> (MemoryForkTree<Block>, MemoryFlatState<u32, u32, u32>) {
+    let mut fork_tree = MemoryForkTree::new();
+    let mut state = MemoryFlatState::new();
+
+    for number in 0..=2 {
+        fork_tree
+            .insert(Block { number })
+            .expect("parent is inserted");
+        state
+            .apply(vec![(number, Some(number))].into_iter(), number, &fork_tree)
+            .expect("block is inserted");
+    }
+
+    (fork_tree, state)
+}
+
+/// Block identified by its fork and depth.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, core::hash::Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlockId {
+    fork: u32,
+    number: u32,
+}
+
+/// Block on a forked chain.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ForkedBlock {
+    pub id: BlockId,
+    pub parent_id: Option<BlockId>,
+}
+
+impl Identified for ForkedBlock {
+    type Identifier = BlockId;
+
+    fn id(&self) -> BlockId {
+        self.id
+    }
+
+    fn parent_id(&self) -> Option<BlockId> {
+        self.parent_id
+    }
+}
+
+fn id(fork: u32, number: u32) -> BlockId {
+    BlockId { fork, number }
+}
+
+/// Build a main chain of blocks 0 to 20 on fork 0, and a fork 1 branching
+/// off block 10 up to block 17.
+///
+/// Genesis sets keys 0 and 1, block 11 on fork 0 changes key 1, block 12 on
+/// fork 1 changes key 0 and removes key 1, and block 18 on fork 0 sets key 2.
+fn build_forked_chain() -> (
+    MemoryForkTree<ForkedBlock>,
+    MemoryFlatState<u32, u32, BlockId>,
+) {
+    let mut fork_tree = MemoryForkTree::new();
+    let mut state = MemoryFlatState::new();
+
+    let blocks = (0..=20)
+        .map(|number| (0, number, 0))
+        .chain((11..=17).map(|number| (1, number, if number == 11 { 0 } else { 1 })));
+    for (fork, number, parent_fork) in blocks {
+        fork_tree
+            .insert(ForkedBlock {
+                id: id(fork, number),
+                parent_id: number.checked_sub(1).map(|number| id(parent_fork, number)),
+            })
+            .expect("parent is inserted");
+    }
+
+    let changes = vec![
+        (id(0, 0), vec![(0, Some(0)), (1, Some(1))]),
+        (id(0, 11), vec![(1, Some(11))]),
+        (id(1, 12), vec![(0, Some(12)), (1, None)]),
+        (id(0, 18), vec![(2, Some(18))]),
+    ];
+    for (block_id, changeset) in changes {
+        state
+            .apply(changeset.into_iter(), block_id, &fork_tree)
+            .expect("block is inserted");
+    }
+
+    (fork_tree, state)
+}
+
+#[test]
+fn get_multiple_keys() -> Result<(), MemoryForkTreeQueryError> {
+    let (fork_tree, state) = build_chain();
+
+    assert_eq!(
+        state.get_multi(&[2, 0, 3], &2, &fork_tree)?,
+        vec![Some(2), Some(0), None]
+    );
+    assert_eq!(
+        state.get_multi(&[1, 2], &1, &fork_tree)?,
+        vec![Some(1), None]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn export_state_at_block() -> Result<(), MemoryForkTreeQueryError> {
+    let (fork_tree, mut state) = build_chain();
+    state.apply(vec![(0, None)].into_iter(), 2, &fork_tree)?;
+
+    assert_eq!(state.export(&1, &fork_tree)?, vec![(0, 0), (1, 1)]);
+    assert_eq!(state.export(&2, &fork_tree)?, vec![(1, 1), (2, 2)]);
+
+    Ok(())
+}
+
+#[test]
+fn diff_between_blocks() -> Result<(), MemoryForkTreeQueryError> {
+    let (fork_tree, mut state) = build_chain();
+    state.apply(vec![(0, None), (1, Some(1))].into_iter(), 2, &fork_tree)?;
+
+    assert_eq!(
+        state.diff(&0, &2, &fork_tree)?,
+        vec![(0, Some(0), None), (1, None, Some(1)), (2, None, Some(2))]
+    );
+    assert_eq!(
+        state.diff(&2, &1, &fork_tree)?,
+        vec![(0, None, Some(0)), (2, Some(2), None)]
+    );
+    assert!(state.diff(&1, &1, &fork_tree)?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn diff_across_reorg() -> Result<(), MemoryForkTreeQueryError> {
+    let (fork_tree, state) = build_forked_chain();
+
+    assert_eq!(
+        state.diff(&id(0, 20), &id(1, 17), &fork_tree)?,
+        vec![
+            (0, Some(0), Some(12)),
+            (1, Some(11), None),
+            (2, Some(18), None)
+        ]
+    );
+    assert_eq!(
+        state.diff(&id(1, 13), &id(0, 12), &fork_tree)?,
+        vec![(0, Some(12), Some(0)), (1, None, Some(11))]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn overlay_transactions() -> Result<(), MemoryForkTreeQueryError> {
+    let (fork_tree, state) = build_chain();
+    let mut overlay = state.overlayed(2, &fork_tree);
+
+    overlay.insert(0, 10);
+    overlay.start_transaction();
+    overlay.insert(0, 20);
+    overlay.remove(&1);
+    overlay.start_transaction();
+    overlay.insert(2, 30);
+    overlay.commit_transaction().expect("transaction is open");
+    assert_eq!(overlay.get(&0)?, Some(20));
+    assert_eq!(overlay.get(&1)?, None);
+    assert_eq!(overlay.get(&2)?, Some(30));
+
+    // Rolling back the outer transaction also discards the committed inner
+    // transaction.
+    overlay.rollback_transaction().expect("transaction is open");
+    assert_eq!(overlay.get(&0)?, Some(10));
+    assert_eq!(overlay.get(&1)?, Some(1));
+    assert_eq!(overlay.get(&2)?, Some(2));
+    assert_eq!(overlay.rollback_transaction(), Err(NoOpenTransaction));
+
+    let mut changeset = overlay.into_changeset().collect::<Vec<_>>();
+    changeset.sort();
+    assert_eq!(changeset, vec![(0, Some(10))]);
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() -> Result<(), MemoryForkTreeQueryError> {
+    use blockchain::ForkTree;
+
+    let snapshot = serde_json::to_string(&build_chain()).expect("chain is serializable");
+    let (fork_tree, state): (MemoryForkTree<Block>, MemoryFlatState<u32, u32, u32>) =
+        serde_json::from_str(&snapshot).expect("snapshot is valid");
+
+    assert_eq!(fork_tree.block_depth(&2)?, 2);
+    assert_eq!(state.get(&1, &2, &fork_tree)?, Some(1));
+    assert_eq!(state.get(&2, &1, &fork_tree)?, None);
+
+    // Snapshots repeating a block are rejected.
+    assert!(
+        serde_json::from_str::<MemoryForkTree<Block>>(r#"[{"number":0},{"number":0}]"#).is_err()
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip_with_forks() -> Result<(), MemoryForkTreeQueryError> {
+    use blockchain::{ForkTree, ForkTreeLeaves};
+
+    let snapshot = serde_json::to_string(&build_forked_chain()).expect("chain is serializable");
+    let (fork_tree, state): (
+        MemoryForkTree<ForkedBlock>,
+        MemoryFlatState<u32, u32, BlockId>,
+    ) = serde_json::from_str(&snapshot).expect("snapshot is valid");
+
+    // Snapshots do not depend on hash map ordering.
+    assert_eq!(
+        serde_json::to_string(&(&fork_tree, &state)).expect("chain is serializable"),
+        snapshot
+    );
+
+    let mut leaves = fork_tree.leaves()?;
+    leaves.sort();
+    assert_eq!(leaves, vec![id(0, 20), id(1, 17)]);
+    assert_eq!(fork_tree.ancestor_id_at_depth(&id(0, 20), 3)?, id(0, 3));
+    assert_eq!(fork_tree.ancestor_id_at_depth(&id(1, 17), 0)?, id(0, 0));
+
+    assert_eq!(state.get(&0, &id(1, 17), &fork_tree)?, Some(12));
+    assert_eq!(state.get(&1, &id(1, 17), &fork_tree)?, None);
+    assert_eq!(state.get(&1, &id(0, 20), &fork_tree)?, Some(11));
+
+    Ok(())
+}
@@ -0,0 +1,202 @@
+//! Fork tree queries over a small tree with a single fork.
+
+use blockchain::memory::{MemoryForkTree, MemoryForkTreeInsertError, MemoryForkTreeQueryError};
+use blockchain::{ForkTree, ForkTreeLeaves, ForkTreeMut, Identified};
+
+/// Block identified by its fork and depth.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, core::hash::Hash)]
+pub struct BlockId {
+    fork: u32,
+    number: u32,
+}
+
+/// Block that only carries its identity.
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub id: BlockId,
+    pub parent_id: Option<BlockId>,
+}
+
+impl Identified for Block {
+    type Identifier = BlockId;
+
+    fn id(&self) -> BlockId {
+        self.id
+    }
+
+    fn parent_id(&self) -> Option<BlockId> {
+        self.parent_id
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Error {
+    Insert(MemoryForkTreeInsertError),
+    Query(MemoryForkTreeQueryError),
+}
+
+impl From<MemoryForkTreeInsertError> for Error {
+    fn from(err: MemoryForkTreeInsertError) -> Self {
+        Self::Insert(err)
+    }
+}
+
+impl From<MemoryForkTreeQueryError> for Error {
+    fn from(err: MemoryForkTreeQueryError) -> Self {
+        Self::Query(err)
+    }
+}
+
+fn id(fork: u32, number: u32) -> BlockId {
+    BlockId { fork, number }
+}
+
+/// Build a main chain of blocks 0 to 20 on fork 0, and a fork 1 branching
+/// off block 10 up to block 17.
+fn build_fork_tree() -> Result<MemoryForkTree<Block>, MemoryForkTreeInsertError> {
+    let mut fork_tree = MemoryForkTree::new();
+
+    fork_tree.insert(Block {
+        id: id(0, 0),
+        parent_id: None,
+    })?;
+    for number in 1..=20 {
+        fork_tree.insert(Block {
+            id: id(0, number),
+            parent_id: Some(id(0, number - 1)),
+        })?;
+    }
+    for number in 11..=17 {
+        let parent_fork = if number == 11 { 0 } else { 1 };
+        fork_tree.insert(Block {
+            id: id(1, number),
+            parent_id: Some(id(parent_fork, number - 1)),
+        })?;
+    }
+
+    Ok(fork_tree)
+}
+
+#[test]
+fn ancestry_queries() -> Result<(), Error> {
+    let fork_tree = build_fork_tree()?;
+
+    assert_eq!(fork_tree.ancestor_id_at_depth(&id(0, 20), 3)?, id(0, 3));
+    assert_eq!(fork_tree.ancestor_id_at_depth(&id(1, 17), 10)?, id(0, 10));
+    assert!(fork_tree.is_ancestor(&id(1, 17), &id(0, 4))?);
+    assert!(!fork_tree.is_ancestor(&id(1, 17), &id(0, 11))?);
+
+    let ancestors = fork_tree
+        .ancestors(&id(1, 13))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(ancestors.len(), 13);
+    assert_eq!(ancestors[0], id(1, 12));
+    assert_eq!(ancestors[2], id(0, 10));
+    assert_eq!(ancestors[12], id(0, 0));
+
+    assert_eq!(
+        fork_tree.common_ancestor(&id(0, 20), &id(1, 17))?,
+        Some(id(0, 10))
+    );
+    assert_eq!(
+        fork_tree.common_ancestor(&id(0, 16), &id(0, 4))?,
+        Some(id(0, 4))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn leaves_are_tracked_on_insert() -> Result<(), Error> {
+    let fork_tree = build_fork_tree()?;
+
+    let mut leaves = fork_tree.leaves()?;
+    leaves.sort_by_key(|id| id.fork);
+    assert_eq!(leaves, vec![id(0, 20), id(1, 17)]);
+
+    Ok(())
+}
+
+#[test]
+fn insert_existing_block_is_rejected() -> Result<(), Error> {
+    let mut fork_tree = build_fork_tree()?;
+    let mut leaves = fork_tree.leaves()?;
+    leaves.sort_by_key(|id| id.fork);
+    let info = fork_tree.info();
+
+    let result = fork_tree.insert(Block {
+        id: id(0, 10),
+        parent_id: Some(id(0, 9)),
+    });
+    assert!(matches!(
+        result,
+        Err(MemoryForkTreeInsertError::AlreadyExists)
+    ));
+    let mut new_leaves = fork_tree.leaves()?;
+    new_leaves.sort_by_key(|id| id.fork);
+    assert_eq!(new_leaves, leaves);
+    assert_eq!(fork_tree.info(), info);
+
+    Ok(())
+}
+
+#[test]
+fn tree_route_through_pivot() -> Result<(), Error> {
+    let fork_tree = build_fork_tree()?;
+
+    let route = fork_tree
+        .tree_route(&id(0, 12), &id(1, 13))?
+        .expect("blocks share a root");
+    assert_eq!(route.pivot(), &(id(0, 10), 10));
+    assert_eq!(route.retracted(), &[(id(0, 12), 12), (id(0, 11), 11)]);
+    assert_eq!(
+        route.enacted(),
+        &[(id(1, 11), 11), (id(1, 12), 12), (id(1, 13), 13)]
+    );
+    assert_eq!(format!("{:?}", route.clone()), format!("{:?}", route),);
+    assert_eq!(route.into_iter().count(), 6);
+
+    let route = fork_tree
+        .tree_route(&id(0, 3), &id(0, 5))?
+        .expect("blocks share a root");
+    assert_eq!(route.pivot(), &(id(0, 3), 3));
+    assert!(route.retracted().is_empty());
+    assert_eq!(route.enacted(), &[(id(0, 4), 4), (id(0, 5), 5)]);
+
+    Ok(())
+}
+
+#[test]
+fn tree_info() -> Result<(), Error> {
+    let fork_tree = build_fork_tree()?;
+    let info = fork_tree.info();
+
+    assert_eq!(info.blocks, 28);
+    assert_eq!(info.leaves, 2);
+    assert_eq!(info.max_depth, Some(20));
+    assert_eq!(info.forks, (11..=17).map(|depth| (depth, 2)).collect());
+
+    // Inserting a known block again does not count as a fork.
+    let mut fork_tree = fork_tree;
+    assert!(fork_tree
+        .insert(Block {
+            id: id(0, 1),
+            parent_id: Some(id(0, 0)),
+        })
+        .is_err());
+    assert_eq!(fork_tree.info(), info);
+
+    Ok(())
+}
+
+#[test]
+fn insert_error_chains_query_error() {
+    use std::error::Error as _;
+
+    let err = MemoryForkTreeInsertError::from(MemoryForkTreeQueryError::UnknownBlock);
+    assert_eq!(
+        err.source().map(|source| source.to_string()),
+        Some("unknown block".to_string())
+    );
+    assert!(MemoryForkTreeInsertError::UnknownParent.source().is_none());
+}
@@ -5,8 +5,8 @@ use blockchain::memory::{
     MemoryTransactional,
 };
 use blockchain::{
-    BlockBuilder, FlatState, FlatStateMut, ForkTree, ForkTreeMut, Headered, Identified,
-    ImportBlock, Keyed, OverlayedFlatState,
+    BlockBuilder, BlockVerifier, Bodied, FlatState, FlatStateMut, ForkTree, ForkTreeMut, Headered,
+    Identified, ImportBlock, Keyed, OverlayedFlatState,
 };
 
 /// A simple seal.
@@ -82,6 +82,14 @@ impl Headered for Block {
     }
 }
 
+impl Bodied for Block {
+    type Extrinsic = Extrinsic;
+
+    fn extrinsics(&self) -> &[Extrinsic] {
+        &self.extrinsics
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ChainData {
     pub fork_tree: MemoryForkTree<Block>,
@@ -97,6 +105,7 @@ pub struct Chain {
 #[derive(Debug, Clone)]
 pub enum ChainError {
     InvalidSeal,
+    InvalidNumber,
     CantImportGenesis,
     ForkTreeInsert(MemoryForkTreeInsertError),
     ForkTreeQuery(MemoryForkTreeQueryError),
@@ -114,23 +123,45 @@ impl From<MemoryForkTreeQueryError> for ChainError {
     }
 }
 
-impl ImportBlock for Chain {
+/// Verifier that only accepts valid seals and numbers following the parent.
+pub struct SealVerifier;
+
+impl BlockVerifier for SealVerifier {
+    type Chain = ChainData;
     type Block = Block;
+    type VerifiedBlock = Block;
     type Error = ChainError;
 
-    fn import(&mut self, block: Block) -> Result<(), Self::Error> {
-        // Verify the seal is valid.
+    fn verify(&self, chain: &ChainData, block: Block) -> Result<Block, Self::Error> {
         if block.seal != Seal::ValidSeal {
             return Err(ChainError::InvalidSeal);
         }
 
+        let parent_id = block.parent_id().ok_or(ChainError::CantImportGenesis)?;
+        let parent_block = chain.fork_tree.block(&parent_id)?;
+        if block.number != parent_block.number + 1 {
+            return Err(ChainError::InvalidNumber);
+        }
+
+        Ok(block)
+    }
+}
+
+impl ImportBlock for Chain {
+    type Block = Block;
+    type Error = ChainError;
+
+    fn import(&mut self, block: Block) -> Result<(), Self::Error> {
+        // Verify the seal and number are valid.
+        let block = SealVerifier.verify(&self.data, block)?;
+
         self.data.apply(|data| {
             let parent_id = block.parent_id().ok_or(ChainError::CantImportGenesis)?;
 
             data.fork_tree.insert(block.clone())?;
 
             let mut overlay = data.state.overlayed(parent_id, &data.fork_tree);
-            for extrinsic in &block.extrinsics {
+            for extrinsic in block.extrinsics() {
                 match extrinsic {
                     Extrinsic::Set(key, value) => {
                         overlay.insert(*key, *value);
@@ -230,7 +261,7 @@ fn basic_build_and_import() -> Result<(), ChainError> {
 
         // It's possible to handle extrinsics in a genesis, but it's a rare thing,
         // and here we just assert that it's empty.
-        assert!(genesis_block.extrinsics.is_empty());
+        assert!(genesis_block.extrinsics().is_empty());
 
         data.state.apply(
             genesis_state.clone().into_iter(),
@@ -265,6 +296,31 @@ fn basic_build_and_import() -> Result<(), ChainError> {
         Some(200),
     );
 
+    // Blocks with an invalid seal or number are rejected without changing
+    // the fork tree.
+    let blocks = chain.data.fork_tree.info().blocks;
+    let mut builder = ChainBlockBuilder::initialize(&chain, block.id(), ())?;
+    builder.apply_extrinsic(Extrinsic::Set(100, 400))?;
+    let invalid_seal_block = builder.finalize(Seal::InvalidSeal)?;
+    assert!(matches!(
+        chain.import(invalid_seal_block.clone()),
+        Err(ChainError::InvalidSeal)
+    ));
+    assert!(chain
+        .data
+        .fork_tree
+        .block(&invalid_seal_block.id())
+        .is_err());
+
+    let mut invalid_number_block = invalid_seal_block.clone();
+    invalid_number_block.seal = Seal::ValidSeal;
+    invalid_number_block.number += 1;
+    assert!(matches!(
+        chain.import(invalid_number_block),
+        Err(ChainError::InvalidNumber)
+    ));
+    assert_eq!(chain.data.fork_tree.info().blocks, blocks);
+
     // Create a fork.
     let mut block2 = block.clone();
     block2.id = BlockId {